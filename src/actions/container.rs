@@ -0,0 +1,50 @@
+use anyhow::{anyhow, Result};
+use std::process::Command;
+
+/// Directory (relative to the instance root) that built packages are collected into
+pub fn get_output_directory(sep_mount: bool) -> &'static str {
+    if sep_mount {
+        "OUTPUT"
+    } else {
+        "../OUTPUT"
+    }
+}
+
+/// Mount the overlay filesystem for `instance`, creating it first if needed
+pub fn mount_fs(instance: &str) -> Result<()> {
+    let status = Command::new("ciel").args(["mount", instance]).status()?;
+    if !status.success() {
+        return Err(anyhow!("failed to mount instance `{}`", instance));
+    }
+    Ok(())
+}
+
+/// Roll the container instance back to its pristine snapshot, discarding any state left over
+/// from a previous build
+pub fn rollback_container(instance: &str) -> Result<()> {
+    let status = Command::new("ciel").args(["rollback", instance]).status()?;
+    if !status.success() {
+        return Err(anyhow!("failed to roll back instance `{}`", instance));
+    }
+    Ok(())
+}
+
+/// Run `cmd` inside `instance`'s container as a transient systemd scope, applying the given
+/// `--property=` scope properties (e.g. `CPUQuota=`, `MemoryMax=`, `CPUWeight=`) and returning
+/// the child's exit code
+pub fn run_in_container(instance: &str, cmd: &[&str], properties: &[String]) -> Result<i32> {
+    // scope properties must come before the command, or systemd-run passes them to it
+    let mut args = properties.to_vec();
+    args.push("shell".to_string());
+    args.push(instance.to_string());
+    args.push("--".to_string());
+    args.extend(cmd.iter().map(|s| s.to_string()));
+
+    let status = Command::new("systemd-run")
+        .arg(format!("--unit=ciel-{}", instance))
+        .arg("--scope")
+        .args(&args)
+        .status()?;
+
+    Ok(status.code().unwrap_or(-1))
+}
@@ -0,0 +1,6 @@
+pub mod container;
+pub mod packaging;
+
+/// Shell script run inside a worker's container before each build to refresh the local
+/// repository index
+pub const UPDATE_SCRIPT: &str = "apt-get update || true";
@@ -1,10 +1,14 @@
 use anyhow::{anyhow, Result};
 use chrono::Duration;
 use console::style;
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::{HashMap, HashSet, VecDeque},
     fs,
     io::{BufRead, BufReader},
-    path::Path,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    thread,
     time::Instant,
 };
 use walkdir::WalkDir;
@@ -16,6 +20,149 @@ use super::{
     UPDATE_SCRIPT,
 };
 
+/// A single entry in the Chrome Trace Event Format (the "complete" `X` phase)
+///
+/// See <https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU>
+#[derive(Serialize)]
+struct TraceEvent {
+    name: String,
+    cat: &'static str,
+    ph: &'static str,
+    ts: u128,
+    dur: u128,
+    pid: u32,
+    tid: u32,
+}
+
+impl TraceEvent {
+    fn new(
+        name: String,
+        cat: &'static str,
+        build_start: Instant,
+        phase_start: Instant,
+        phase_end: Instant,
+    ) -> Self {
+        TraceEvent {
+            name,
+            cat,
+            ph: "X",
+            ts: phase_start.duration_since(build_start).as_micros(),
+            dur: phase_end.duration_since(phase_start).as_micros(),
+            pid: 1,
+            tid: 1,
+        }
+    }
+}
+
+/// Write the collected trace events to `path` as a Trace Event Format JSON array
+fn write_trace<P: AsRef<Path>>(path: P, events: &[TraceEvent]) -> Result<()> {
+    let f = fs::File::create(path)?;
+    serde_json::to_writer(f, events)?;
+
+    Ok(())
+}
+
+/// cgroup resource limits applied to the container while building a package
+#[derive(Default, Clone)]
+struct ResourceLimits {
+    cpu_quota: Option<String>,
+    memory_max: Option<String>,
+    cpu_weight: Option<String>,
+}
+
+impl ResourceLimits {
+    /// Build the `systemd-run --property=` flags for the limits that are set
+    fn to_properties(&self) -> Vec<String> {
+        let mut properties = Vec::new();
+        if let Some(quota) = &self.cpu_quota {
+            properties.push(format!("--property=CPUQuota={}", quota));
+        }
+        if let Some(memory_max) = &self.memory_max {
+            properties.push(format!("--property=MemoryMax={}", memory_max));
+        }
+        if let Some(weight) = &self.cpu_weight {
+            properties.push(format!("--property=CPUWeight={}", weight));
+        }
+
+        properties
+    }
+}
+
+/// Name of the on-disk checkpoint file used by `--resume`
+const BUILD_STATE_FILE: &str = ".ciel-build-state.json";
+
+/// Progress of a `package_build` run, persisted so it can be resumed after a crash or Ctrl-C
+#[derive(Serialize, Deserialize)]
+struct BuildCheckpoint {
+    packages: Vec<String>,
+    completed: Vec<String>,
+}
+
+impl BuildCheckpoint {
+    fn new(packages: Vec<String>) -> Self {
+        BuildCheckpoint {
+            packages,
+            completed: Vec::new(),
+        }
+    }
+
+    fn load<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let f = fs::File::open(path).ok()?;
+        serde_json::from_reader(f).ok()
+    }
+
+    fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let f = fs::File::create(path)?;
+        serde_json::to_writer(f, self)?;
+
+        Ok(())
+    }
+}
+
+/// The outcome of building a single package, used to build the end-of-run summary
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BuildOutcome {
+    Success,
+    Failed,
+    /// Never attempted because one of its in-set build-dependencies failed, because its
+    /// in-set build-dependencies never resolved (e.g. a cyclic BUILDDEP/PKGDEP), or because a
+    /// parallel run stopped dispatching after a failure without `--keep-going`
+    Skipped,
+}
+
+/// Print a colored summary table of a `--keep-going` run, or of a parallel run with failures
+fn print_build_summary(outcomes: &[(String, BuildOutcome)]) {
+    let failed: Vec<&str> = outcomes
+        .iter()
+        .filter(|(_, outcome)| *outcome == BuildOutcome::Failed)
+        .map(|(package, _)| package.as_str())
+        .collect();
+    let skipped = outcomes
+        .iter()
+        .filter(|(_, outcome)| *outcome == BuildOutcome::Skipped)
+        .count();
+    let succeeded = outcomes.len() - failed.len() - skipped;
+
+    eprintln!();
+    eprintln!(
+        "{} {} succeeded, {} failed, {} skipped",
+        style("BUILD SUMMARY").bold(),
+        style(succeeded).green(),
+        if failed.is_empty() {
+            style(failed.len()).green()
+        } else {
+            style(failed.len()).red()
+        },
+        skipped
+    );
+    if !failed.is_empty() {
+        eprintln!("{}", style("Failed packages:").red());
+        for package in failed {
+            eprintln!("  - {}", style(package).red());
+        }
+    }
+}
+
 #[inline]
 fn format_duration(duration: Duration) -> String {
     let seconds = duration.num_seconds();
@@ -80,11 +227,290 @@ fn expand_package_list<'a, I: IntoIterator<Item = &'a str>>(packages: I) -> Vec<
     expanded
 }
 
-/// Fetch all the source packages in one go
+/// Best-effort extraction of a package's in-set build dependencies from its ABBS
+/// `spec`/`autobuild/defines` files, so independent packages can be scheduled in parallel
+fn parse_build_deps(package: &str, in_set: &HashSet<String>) -> HashSet<String> {
+    let mut deps = HashSet::new();
+    for filename in ["spec", "autobuild/defines"] {
+        let path = Path::new("./TREE").join(package).join(filename);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        for line in content.lines() {
+            let line = line.trim();
+            let value = match line
+                .strip_prefix("BUILDDEP=")
+                .or_else(|| line.strip_prefix("PKGDEP="))
+            {
+                Some(value) => value,
+                None => continue,
+            };
+            for dep in value.trim_matches('"').split_whitespace() {
+                if in_set.contains(dep) {
+                    deps.insert(dep.to_string());
+                }
+            }
+        }
+    }
+
+    deps
+}
+
+/// Per-worker outcome of a single package build, sent back to the scheduler
+struct WorkerResult {
+    worker_id: usize,
+    package: String,
+    status: i32,
+    events: Vec<TraceEvent>,
+}
+
+/// Build an expanded, dependency-ordered package list across a bounded pool of workers
+///
+/// Packages with no in-set build-dependencies on each other are built concurrently, each in
+/// its own rolled-back container instance; the `repo::init_repo`/`UPDATE_SCRIPT` refresh is
+/// serialized behind `repo_refresh_lock` since `local_repo` is shared state.
+#[allow(clippy::too_many_arguments)]
+fn package_build_parallel(
+    instance: &str,
+    root: &Path,
+    packages: Vec<String>,
+    jobs: usize,
+    keep_going: bool,
+    mut checkpoint: BuildCheckpoint,
+    checkpoint_path: &Path,
+    trace: Option<&Path>,
+    limit_properties: &[String],
+) -> Result<i32> {
+    let total = packages.len();
+    let in_set: HashSet<String> = packages.iter().cloned().collect();
+    let mut dependents: HashMap<String, Vec<String>> = HashMap::new();
+    let mut remaining_deps: HashMap<String, usize> = HashMap::new();
+    for package in &packages {
+        let deps = parse_build_deps(package, &in_set);
+        remaining_deps.insert(package.clone(), deps.len());
+        for dep in &deps {
+            dependents.entry(dep.clone()).or_default().push(package.clone());
+        }
+    }
+
+    let mut outcomes: Vec<(String, BuildOutcome)> = Vec::new();
+    let mut ready: VecDeque<String> = VecDeque::new();
+    for package in &packages {
+        if checkpoint.completed.contains(package) {
+            outcomes.push((package.clone(), BuildOutcome::Success));
+        } else if remaining_deps[package] == 0 {
+            ready.push_back(package.clone());
+        }
+    }
+    // already-completed packages don't block their dependents
+    for package in &checkpoint.completed {
+        if let Some(next) = dependents.remove(package) {
+            for dependent in next {
+                if let Some(count) = remaining_deps.get_mut(&dependent) {
+                    *count -= 1;
+                    if *count == 0 && !checkpoint.completed.contains(&dependent) {
+                        ready.push_back(dependent);
+                    }
+                }
+            }
+        }
+    }
+
+    let repo_refresh_lock = Arc::new(Mutex::new(()));
+    let (result_tx, result_rx) = mpsc::channel::<WorkerResult>();
+    let mut worker_tx = Vec::with_capacity(jobs);
+    let mut handles = Vec::with_capacity(jobs);
+    let start = Instant::now();
+
+    for worker_id in 0..jobs {
+        let (tx, rx) = mpsc::channel::<String>();
+        worker_tx.push(tx);
+        let result_tx = result_tx.clone();
+        let worker_instance = format!("{}-j{}", instance, worker_id);
+        let root: PathBuf = root.to_path_buf();
+        let repo_refresh_lock = Arc::clone(&repo_refresh_lock);
+        let limit_properties = limit_properties.to_vec();
+        handles.push(thread::spawn(move || {
+            for package in rx {
+                // Run this package's build in a closure so that any `Err` (as opposed to a
+                // non-zero exit status, which is reported normally) still produces a
+                // `WorkerResult` -- otherwise the scheduler would wait forever on a package
+                // whose worker silently died.
+                let mut events = Vec::new();
+                let attempt: Result<i32> = (|| {
+                    mount_fs(&worker_instance)?;
+                    rollback_container(&worker_instance)?;
+
+                    let refresh_start = Instant::now();
+                    let mut status = {
+                        let _guard = repo_refresh_lock.lock().unwrap();
+                        info!("Refreshing local repository...");
+                        repo::init_repo(&root, Path::new(&worker_instance))?;
+                        run_in_container(&worker_instance, &["/bin/bash", "-ec", UPDATE_SCRIPT], &[])?
+                    };
+                    events.push(TraceEvent::new(
+                        package.clone(),
+                        "repo-refresh",
+                        start,
+                        refresh_start,
+                        Instant::now(),
+                    ));
+
+                    if status == 0 {
+                        let build_start = Instant::now();
+                        status = run_in_container(
+                            &worker_instance,
+                            &["/bin/acbs-build", "--", &package],
+                            &limit_properties,
+                        )?;
+                        events.push(TraceEvent::new(
+                            package.clone(),
+                            "build",
+                            start,
+                            build_start,
+                            Instant::now(),
+                        ));
+                    }
+                    rollback_container(&worker_instance)?;
+
+                    Ok(status)
+                })();
+
+                let status = attempt.unwrap_or_else(|e| {
+                    error!("Worker error while building {}: {}", package, e);
+                    1
+                });
+
+                if result_tx
+                    .send(WorkerResult {
+                        worker_id,
+                        package,
+                        status,
+                        events,
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }));
+    }
+    drop(result_tx);
+
+    let mut idle_workers: VecDeque<usize> = (0..jobs).collect();
+    let mut in_flight = 0usize;
+    let mut aborted = false;
+    let mut trace_events: Vec<TraceEvent> = Vec::new();
+
+    loop {
+        while (!aborted || keep_going) && !ready.is_empty() && !idle_workers.is_empty() {
+            let package = ready.pop_front().unwrap();
+            let worker_id = idle_workers.pop_front().unwrap();
+            worker_tx[worker_id].send(package)?;
+            in_flight += 1;
+        }
+        if in_flight == 0 {
+            break;
+        }
+        let result = match result_rx.recv() {
+            Ok(result) => result,
+            Err(_) => break,
+        };
+        in_flight -= 1;
+        idle_workers.push_back(result.worker_id);
+        trace_events.extend(result.events);
+
+        let success = result.status == 0;
+        if !success {
+            error!(
+                "Build failed with status: {} ({})",
+                result.status, result.package
+            );
+            aborted = true;
+        } else {
+            checkpoint.completed.push(result.package.clone());
+            checkpoint.save(checkpoint_path)?;
+            if let Some(next) = dependents.remove(&result.package) {
+                for dependent in next {
+                    if let Some(count) = remaining_deps.get_mut(&dependent) {
+                        *count -= 1;
+                        if *count == 0 && (!aborted || keep_going) {
+                            ready.push_back(dependent);
+                        }
+                    }
+                }
+            }
+        }
+        outcomes.push((
+            result.package,
+            if success {
+                BuildOutcome::Success
+            } else {
+                BuildOutcome::Failed
+            },
+        ));
+    }
+
+    drop(worker_tx);
+    for handle in handles {
+        handle.join().expect("build worker thread panicked");
+    }
+
+    // Packages whose in-set dependencies never reached zero -- either because the run was
+    // aborted without --keep-going, or because parse_build_deps found a cycle that the
+    // scheduler could never resolve. Either way the requested set wasn't fully built, so
+    // these must count as failures rather than being reported as a clean success.
+    let built: HashSet<&str> = outcomes.iter().map(|(package, _)| package.as_str()).collect();
+    for package in &packages {
+        if !built.contains(package.as_str()) {
+            outcomes.push((package.clone(), BuildOutcome::Skipped));
+        }
+    }
+
+    if let Some(trace_path) = trace {
+        write_trace(trace_path, &trace_events)?;
+    }
+    let failed_count = outcomes
+        .iter()
+        .filter(|(_, outcome)| matches!(outcome, BuildOutcome::Failed | BuildOutcome::Skipped))
+        .count();
+    if failed_count == 0 {
+        let _ = fs::remove_file(checkpoint_path);
+    }
+    if keep_going || failed_count > 0 {
+        print_build_summary(&outcomes);
+    }
+    let duration = Duration::from_std(start.elapsed())?;
+    eprintln!(
+        "{} - {} packages in {}",
+        if failed_count == 0 {
+            style("BUILD SUCCESSFUL").bold().green()
+        } else {
+            style("BUILD FINISHED WITH FAILURES").bold().red()
+        },
+        total,
+        format_duration(duration)
+    );
+
+    Ok(if failed_count == 0 { 0 } else { 1 })
+}
+
+/// Result of fetching the sources of a single package
+struct FetchResult {
+    package: String,
+    success: bool,
+}
+
+/// Fetch all the source packages, up to `jobs` at a time, and report per-package results
+///
+/// Returns the list of packages whose sources could not be fetched, so offline-mode callers
+/// can report exactly what's missing instead of failing opaquely once network access is cut.
 pub fn package_fetch<'a, K: ExactSizeIterator<Item = &'a str>>(
     instance: &str,
     packages: K,
-) -> Result<i32> {
+    jobs: usize,
+) -> Result<Vec<String>> {
     let conf = config::read_config();
     if conf.is_err() {
         return Err(anyhow!("Please configure this workspace first!"));
@@ -94,31 +520,113 @@ pub fn package_fetch<'a, K: ExactSizeIterator<Item = &'a str>>(
         warn!("Using this function without local sources caching is probably meaningless.");
     }
 
-    mount_fs(instance)?;
-    rollback_container(instance)?;
+    let packages = expand_package_list(packages);
+    let total = packages.len();
+    let jobs = jobs.max(1);
+
+    let (work_tx, work_rx) = mpsc::channel::<String>();
+    for package in packages {
+        work_tx.send(package)?;
+    }
+    drop(work_tx);
+    let work_rx = Arc::new(Mutex::new(work_rx));
+
+    let (result_tx, result_rx) = mpsc::channel::<FetchResult>();
+    let mut handles = Vec::with_capacity(jobs);
+    for worker_id in 0..jobs {
+        let work_rx = Arc::clone(&work_rx);
+        let result_tx = result_tx.clone();
+        // each worker gets its own instance, same as `package_build_parallel`, since running
+        // several `acbs-build` invocations at once inside one systemd-nspawn instance collides
+        let worker_instance = format!("{}-f{}", instance, worker_id);
+        handles.push(thread::spawn(move || loop {
+            let package = match work_rx.lock().unwrap().recv() {
+                Ok(package) => package,
+                Err(_) => break,
+            };
+            let attempt: Result<i32> = (|| {
+                mount_fs(&worker_instance)?;
+                rollback_container(&worker_instance)?;
+                let status = run_in_container(
+                    &worker_instance,
+                    &["/bin/acbs-build", "-g", "--", &package],
+                    &[],
+                )?;
+                rollback_container(&worker_instance)?;
+
+                Ok(status)
+            })();
+            let success = attempt.unwrap_or_else(|e| {
+                error!("Worker error while fetching {}: {}", package, e);
+                1
+            }) == 0;
 
-    let mut cmd = vec!["/bin/acbs-build", "-g", "--"];
-    cmd.extend(packages.into_iter());
-    let status = run_in_container(instance, &cmd)?;
+            if result_tx.send(FetchResult { package, success }).is_err() {
+                break;
+            }
+        }));
+    }
+    drop(result_tx);
+
+    let results: Vec<FetchResult> = result_rx.into_iter().collect();
+    for handle in handles {
+        handle.join().expect("fetch worker thread panicked");
+    }
+
+    let failed: Vec<String> = results
+        .iter()
+        .filter(|result| !result.success)
+        .map(|result| result.package.clone())
+        .collect();
+    info!(
+        "Fetched sources: {} succeeded, {} failed",
+        total - failed.len(),
+        failed.len()
+    );
+    if !failed.is_empty() {
+        warn!("Missing sources for: {}", failed.join(", "));
+    }
 
-    Ok(status)
+    Ok(failed)
 }
 
 /// Build packages in the container
+#[allow(clippy::too_many_arguments)]
 pub fn package_build<'a, K: Clone + ExactSizeIterator<Item = &'a str>>(
     instance: &str,
     packages: K,
     offline: bool,
+    trace: Option<&Path>,
+    keep_going: bool,
+    resume: bool,
+    cpu_quota: Option<String>,
+    memory_max: Option<String>,
+    cpu_weight: Option<String>,
+    jobs: usize,
 ) -> Result<i32> {
     let conf = config::read_config();
     if conf.is_err() {
         return Err(anyhow!("Please configure this workspace first!"));
     }
     let conf = conf.unwrap();
+    let limits = ResourceLimits {
+        cpu_quota: cpu_quota.or(conf.build_cpu_quota.clone()),
+        memory_max: memory_max.or(conf.build_memory_max.clone()),
+        cpu_weight: cpu_weight.or(conf.build_cpu_weight.clone()),
+    };
+    let limit_properties = limits.to_properties();
 
     if offline || std::env::var("CIEL_OFFLINE").is_ok() {
         info!("Preparing offline mode. Fetching source packages first ...");
-        package_fetch(&instance, packages.clone())?;
+        let missing = package_fetch(&instance, packages.clone(), jobs.max(1))?;
+        if !missing.is_empty() {
+            error!(
+                "Cannot continue offline: missing sources for {} package(s): {}",
+                missing.len(),
+                missing.join(", ")
+            );
+            return Ok(1);
+        }
         std::env::set_var("CIEL_OFFLINE", "ON");
         // FIXME: does not work with current version of systemd
         info!("Running in offline mode. Network access disabled.");
@@ -130,7 +638,7 @@ pub fn package_build<'a, K: Clone + ExactSizeIterator<Item = &'a str>>(
     if !conf.local_repo {
         let mut cmd = vec!["/bin/acbs-build", "--"];
         cmd.extend(packages.into_iter());
-        let status = run_in_container(instance, &cmd)?;
+        let status = run_in_container(instance, &cmd, &limit_properties)?;
         return Ok(status);
     }
 
@@ -138,36 +646,120 @@ pub fn package_build<'a, K: Clone + ExactSizeIterator<Item = &'a str>>(
     let root = std::env::current_dir()?.join(output_dir);
     let packages = expand_package_list(packages);
     let total = packages.len();
+    let checkpoint_path = std::env::current_dir()?.join(BUILD_STATE_FILE);
+    let mut checkpoint = if resume {
+        BuildCheckpoint::load(&checkpoint_path)
+            .filter(|checkpoint| checkpoint.packages == packages)
+            .unwrap_or_else(|| BuildCheckpoint::new(packages.clone()))
+    } else {
+        BuildCheckpoint::new(packages.clone())
+    };
+
+    if jobs > 1 {
+        return package_build_parallel(
+            instance,
+            &root,
+            packages,
+            jobs,
+            keep_going,
+            checkpoint,
+            &checkpoint_path,
+            trace,
+            &limit_properties,
+        );
+    }
+
     let start = Instant::now();
+    let mut trace_events: Vec<TraceEvent> = Vec::new();
+    let mut outcomes: Vec<(String, BuildOutcome)> = Vec::new();
     for (index, package) in packages.into_iter().enumerate() {
+        if resume && checkpoint.completed.contains(&package) {
+            info!("[{}/{}] Skipping {} (already built)", index + 1, total, package);
+            outcomes.push((package, BuildOutcome::Success));
+            continue;
+        }
         // set terminal title, \r is for hiding the message if the terminal does not support the sequence
         eprint!("\x1b]0;ciel: [{}/{}] {}\x07\r", index + 1, total, package);
         // hopefully the sequence gets flushed together with the `info!` below
         info!("[{}/{}] Building {}...", index + 1, total, package);
         mount_fs(&instance)?;
         info!("Refreshing local repository...");
+        let refresh_start = Instant::now();
         repo::init_repo(&root, Path::new(instance))?;
-        let status = run_in_container(&instance, &["/bin/bash", "-ec", UPDATE_SCRIPT])?;
+        let status = run_in_container(&instance, &["/bin/bash", "-ec", UPDATE_SCRIPT], &[])?;
+        if trace.is_some() {
+            trace_events.push(TraceEvent::new(
+                package.clone(),
+                "repo-refresh",
+                start,
+                refresh_start,
+                Instant::now(),
+            ));
+        }
         if status != 0 {
             error!("Failed to update the OS before building packages");
-            return Ok(status);
+            if !keep_going {
+                return Ok(status);
+            }
+            outcomes.push((package, BuildOutcome::Failed));
+            rollback_container(instance)?;
+            continue;
+        }
+        let build_start = Instant::now();
+        let status = run_in_container(
+            instance,
+            &["/bin/acbs-build", "--", &package],
+            &limit_properties,
+        )?;
+        if trace.is_some() {
+            trace_events.push(TraceEvent::new(
+                package.clone(),
+                "build",
+                start,
+                build_start,
+                Instant::now(),
+            ));
         }
-        let status = run_in_container(instance, &["/bin/acbs-build", "--", &package])?;
         if status != 0 {
             error!("Build failed with status: {}", status);
-            return Ok(status);
+            if !keep_going {
+                return Ok(status);
+            }
+            outcomes.push((package, BuildOutcome::Failed));
+            rollback_container(instance)?;
+            continue;
         }
+        checkpoint.completed.push(package.clone());
+        checkpoint.save(&checkpoint_path)?;
+        outcomes.push((package, BuildOutcome::Success));
         rollback_container(instance)?;
     }
+    if let Some(trace_path) = trace {
+        write_trace(trace_path, &trace_events)?;
+    }
     let duration = Duration::from_std(start.elapsed())?;
+    let failed_count = outcomes
+        .iter()
+        .filter(|(_, outcome)| *outcome == BuildOutcome::Failed)
+        .count();
+    if failed_count == 0 {
+        let _ = fs::remove_file(&checkpoint_path);
+    }
+    if keep_going {
+        print_build_summary(&outcomes);
+    }
     eprintln!(
         "{} - {} packages in {}",
-        style("BUILD SUCCESSFUL").bold().green(),
+        if failed_count == 0 {
+            style("BUILD SUCCESSFUL").bold().green()
+        } else {
+            style("BUILD FINISHED WITH FAILURES").bold().red()
+        },
         total,
         format_duration(duration)
     );
 
-    Ok(0)
+    Ok(if failed_count == 0 { 0 } else { 1 })
 }
 
 /// Clean up output directories
@@ -190,3 +782,35 @@ fn test_time_format() {
     let test_dur = Duration::seconds(3661);
     assert_eq!(format_duration(test_dur), "01:01:01");
 }
+
+#[test]
+fn test_build_checkpoint_roundtrip() {
+    let path = std::env::temp_dir().join(format!("ciel-test-checkpoint-{}.json", std::process::id()));
+    let mut checkpoint = BuildCheckpoint::new(vec!["a".to_string(), "b".to_string()]);
+    checkpoint.completed.push("a".to_string());
+    checkpoint.save(&path).unwrap();
+
+    let loaded = BuildCheckpoint::load(&path).unwrap();
+    assert_eq!(loaded.packages, checkpoint.packages);
+    assert_eq!(loaded.completed, checkpoint.completed);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_resource_limits_to_properties() {
+    assert!(ResourceLimits::default().to_properties().is_empty());
+
+    let limits = ResourceLimits {
+        cpu_quota: Some("50%".to_string()),
+        memory_max: Some("2G".to_string()),
+        cpu_weight: None,
+    };
+    assert_eq!(
+        limits.to_properties(),
+        vec![
+            "--property=CPUQuota=50%".to_string(),
+            "--property=MemoryMax=2G".to_string(),
+        ]
+    );
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+const CONFIG_FILE: &str = "/etc/ciel.toml";
+
+/// Per-instance configuration, persisted as TOML at `/etc/ciel.toml`
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    pub local_sources: bool,
+    pub local_repo: bool,
+    pub sep_mount: bool,
+    /// Default `CPUQuota=` systemd scope property applied to package builds, unless
+    /// overridden on the command line
+    pub build_cpu_quota: Option<String>,
+    /// Default `MemoryMax=` systemd scope property applied to package builds, unless
+    /// overridden on the command line
+    pub build_memory_max: Option<String>,
+    /// Default `CPUWeight=` systemd scope property applied to package builds, unless
+    /// overridden on the command line
+    pub build_cpu_weight: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            local_sources: false,
+            local_repo: false,
+            sep_mount: true,
+            build_cpu_quota: None,
+            build_memory_max: None,
+            build_cpu_weight: None,
+        }
+    }
+}
+
+/// Read the instance configuration from `/etc/ciel.toml`, falling back to defaults for any
+/// field missing from the file
+pub fn read_config() -> Result<Config> {
+    let content = fs::read_to_string(CONFIG_FILE)?;
+    Ok(toml::from_str(&content)?)
+}